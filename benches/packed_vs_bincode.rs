@@ -0,0 +1,58 @@
+//! Compares the size and throughput of the packed-byte `GameInput` encoding
+//! against the serde/bincode alternative in `src/serde_config.rs`, so users
+//! can weigh the bandwidth cost of switching.
+
+use bytemuck::{Pod, Zeroable};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde::{Deserialize, Serialize};
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct PackedInput {
+    mouse: [f32; 2],
+    has_mouse: u8,
+    keys: u8,
+    _padding: [u8; 6],
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct BincodeInput {
+    mouse: [f32; 2],
+    has_mouse: bool,
+    keys: u8,
+}
+
+fn bench_input_encoding(c: &mut Criterion) {
+    let packed = PackedInput {
+        mouse: [123.4, -56.7],
+        has_mouse: 1,
+        keys: 0b0001_0110,
+        _padding: [0; 6],
+    };
+    let bincode_input = BincodeInput {
+        mouse: [123.4, -56.7],
+        has_mouse: true,
+        keys: 0b0001_0110,
+    };
+
+    println!(
+        "packed: {} bytes, bincode: {} bytes",
+        std::mem::size_of::<PackedInput>(),
+        bincode::serialized_size(&bincode_input).unwrap()
+    );
+
+    let mut group = c.benchmark_group("input_encoding");
+
+    group.bench_function("packed_bytes", |b| {
+        b.iter(|| black_box(bytemuck::bytes_of(&packed)).len())
+    });
+
+    group.bench_function("bincode", |b| {
+        b.iter(|| black_box(bincode::serialize(&bincode_input).unwrap()).len())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_input_encoding);
+criterion_main!(benches);
@@ -10,14 +10,46 @@ use bevy_ggrs::*;
 use bevy_matchbox::matchbox_socket::{PeerId, SingleChannel};
 use bevy_matchbox::MatchboxSocket;
 use bytemuck::{Pod, Zeroable};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "physics")]
+use bevy_rapier2d::prelude::*;
+
+#[cfg(feature = "serde-state")]
+mod serde_config;
+
+/// The packed-byte `GgrsConfig` is the default; with the `serde-state`
+/// feature this swaps in `serde_config::SerdeGgrsConfig` everywhere instead,
+/// the same way the `physics` feature swaps `move_players` for
+/// Rapier-driven movement below.
+#[cfg(not(feature = "serde-state"))]
+type ActiveConfig = GgrsConfig;
+#[cfg(feature = "serde-state")]
+type ActiveConfig = serde_config::SerdeGgrsConfig;
 
 fn main() {
     let mut app = App::new();
 
-    GGRSPlugin::<GgrsConfig>::new()
+    let config = game_config();
+
+    let mut ggrs_plugin = GGRSPlugin::<ActiveConfig>::new()
         .with_input_system(input)
         .register_rollback_component::<Transform>()
-        .build(&mut app);
+        .register_rollback_component::<Checksum>()
+        .with_update_frequency(config.fps);
+
+    #[cfg(feature = "physics")]
+    {
+        ggrs_plugin = ggrs_plugin.register_rollback_resource::<RapierContextSnapshot>();
+    }
+
+    #[cfg(feature = "serde-state")]
+    {
+        ggrs_plugin = ggrs_plugin.register_rollback_resource::<serde_config::GameStateSnapshot>();
+    }
+
+    ggrs_plugin.build(&mut app);
 
     app.add_plugins(DefaultPlugins.set(WindowPlugin {
         primary_window: Some(Window {
@@ -30,9 +62,209 @@ fn main() {
         ..default()
     }))
     .insert_resource(ClearColor(Color::rgb(0.53, 0.53, 0.53)))
-    .add_startup_systems((setup, spawn_players, start_matchbox_socket))
-    .add_systems((move_players.in_schedule(GGRSSchedule), wait_for_players))
-    .run();
+    .add_startup_systems((setup, spawn_players));
+
+    #[cfg(feature = "physics")]
+    {
+        // `with_default_system_setup(false)` stops Rapier from scheduling
+        // its own sets on its own timestep; we schedule the same sets
+        // ourselves, inside `GGRSSchedule`, around our own input/snapshot
+        // systems so every GGRS tick (including resimulated ones) drives
+        // Rapier exactly once. `SyncBackendFlush` (Rapier's own
+        // `apply_system_buffers`) has to stay between `SyncBackend` and
+        // `StepSimulation`, same as the default setup: `SyncBackend` queues
+        // `Commands` that attach `RapierRigidBodyHandle`/collider handles,
+        // and without the flush those inserts aren't visible to
+        // `StepSimulation` until the next tick, so a body spawned this frame
+        // would silently miss this frame's step.
+        app.add_plugin(RapierPhysicsPlugin::<NoUserData>::default().with_default_system_setup(false))
+            .insert_resource(RapierContextSnapshot::default())
+            .add_systems(
+                (
+                    restore_rapier_context,
+                    apply_player_forces,
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackend),
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackendFlush),
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::StepSimulation),
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::Writeback),
+                    snapshot_rapier_context,
+                )
+                    .chain()
+                    .in_schedule(GGRSSchedule),
+            );
+    }
+
+    #[cfg(not(feature = "physics"))]
+    {
+        app.add_system(move_players.in_schedule(GGRSSchedule));
+    }
+
+    // Runs last each tick so it captures the positions the frame actually
+    // settled on, whichever of the two systems above produced them.
+    #[cfg(all(feature = "serde-state", feature = "physics"))]
+    app.insert_resource(serde_config::GameStateSnapshot::default())
+        .add_system(
+            serde_config::save_game_state
+                .in_schedule(GGRSSchedule)
+                .after(snapshot_rapier_context),
+        );
+    #[cfg(all(feature = "serde-state", not(feature = "physics")))]
+    app.insert_resource(serde_config::GameStateSnapshot::default())
+        .add_system(
+            serde_config::save_game_state
+                .in_schedule(GGRSSchedule)
+                .after(move_players),
+        );
+
+    // Runs last each tick so it hashes the positions the frame actually
+    // settled on, whichever of the two systems above produced them.
+    #[cfg(feature = "physics")]
+    app.add_system(
+        compute_checksum
+            .in_schedule(GGRSSchedule)
+            .after(snapshot_rapier_context),
+    );
+    #[cfg(not(feature = "physics"))]
+    app.add_system(
+        compute_checksum
+            .in_schedule(GGRSSchedule)
+            .after(move_players),
+    );
+
+    match session_mode(&config) {
+        SessionMode::P2P => {
+            app.add_startup_system(start_matchbox_socket)
+                .add_system(wait_for_players);
+        }
+        SessionMode::SyncTest => {
+            app.add_startup_system(start_synctest_session);
+        }
+    }
+
+    app.insert_resource(config).run();
+}
+
+/// Tunable session parameters, resolved once at startup from CLI args on
+/// native or from the page's URL query string on wasm, instead of being
+/// hardcoded as they were before.
+#[derive(Resource, Debug, Clone)]
+struct GameConfig {
+    num_players: usize,
+    input_delay: usize,
+    max_prediction_window: usize,
+    fps: usize,
+    room_url: String,
+    synctest: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            num_players: 2,
+            input_delay: 2,
+            max_prediction_window: 8,
+            fps: 60,
+            room_url: "wss://chickenchicken-matchbox.fly.dev/extreme_bevy".to_string(),
+            synctest: false,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(structopt::StructOpt)]
+struct Opt {
+    #[structopt(long)]
+    num_players: Option<usize>,
+    #[structopt(long)]
+    input_delay: Option<usize>,
+    #[structopt(long)]
+    max_prediction_window: Option<usize>,
+    #[structopt(long)]
+    fps: Option<usize>,
+    #[structopt(long)]
+    room_url: Option<String>,
+    #[structopt(long)]
+    synctest: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn game_config() -> GameConfig {
+    use structopt::StructOpt;
+
+    let opt = Opt::from_args();
+    let default = GameConfig::default();
+
+    GameConfig {
+        num_players: opt.num_players.unwrap_or(default.num_players),
+        input_delay: opt.input_delay.unwrap_or(default.input_delay),
+        max_prediction_window: opt
+            .max_prediction_window
+            .unwrap_or(default.max_prediction_window),
+        fps: opt.fps.unwrap_or(default.fps),
+        room_url: opt.room_url.unwrap_or(default.room_url),
+        synctest: opt.synctest,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn game_config() -> GameConfig {
+    let default = GameConfig::default();
+
+    let search = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .unwrap_or_default();
+
+    let params: std::collections::HashMap<String, String> = search
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+    GameConfig {
+        num_players: params
+            .get("num_players")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.num_players),
+        input_delay: params
+            .get("input_delay")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.input_delay),
+        max_prediction_window: params
+            .get("max_prediction_window")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.max_prediction_window),
+        fps: params
+            .get("fps")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default.fps),
+        room_url: params.get("room_url").cloned().unwrap_or(default.room_url),
+        synctest: params
+            .get("synctest")
+            .map(|v| v == "true")
+            .unwrap_or(default.synctest),
+    }
+}
+
+/// Which kind of GGRS session to start at launch.
+///
+/// Pass `--synctest` (native) or `?synctest=true` (wasm) to run fully
+/// locally against GGRS's `SyncTestSession`, which re-simulates recent
+/// frames from a saved snapshot and panics on the first checksum mismatch.
+/// That's the fastest way to catch nondeterministic game logic before it
+/// ever reaches a real match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionMode {
+    P2P,
+    SyncTest,
+}
+
+fn session_mode(config: &GameConfig) -> SessionMode {
+    if config.synctest {
+        SessionMode::SyncTest
+    } else {
+        SessionMode::P2P
+    }
 }
 
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
@@ -63,31 +295,63 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     ));
 }
 
-fn start_matchbox_socket(mut commands: Commands) {
-    let room_url = "wss://chickenchicken-matchbox.fly.dev/extreme_bevy?next=2";
+fn start_matchbox_socket(mut commands: Commands, config: Res<GameConfig>) {
+    // `next` tells the matchbox server how many peers are required before a
+    // match can begin; it doesn't cap the room. Anyone who joins after
+    // `num_players` are in is treated as a spectator in `wait_for_players`.
+    let room_url = format!("{}?next={}", config.room_url, config.num_players);
     info!("connecting to matchbox server: {:?}", room_url);
     commands.insert_resource(MatchboxSocket::new_ggrs(room_url));
 }
 
-fn wait_for_players(mut commands: Commands, mut socket: ResMut<MatchboxSocket<SingleChannel>>) {
+/// Marks that this client joined as a spectator, so `input` knows not to
+/// collect and submit local input for a session it isn't playing in.
+#[derive(Resource, Default)]
+struct Spectating(bool);
+
+fn wait_for_players(
+    mut commands: Commands,
+    mut socket: ResMut<MatchboxSocket<SingleChannel>>,
+    config: Res<GameConfig>,
+) {
     // Check for new connections
     socket.update_peers();
     let players = socket.players();
 
-    let num_players = 2;
+    let num_players = config.num_players;
     if players.len() < num_players {
-        return; // wait for more players
+        return; // wait for more players; spectators may keep trickling in after
+    }
+
+    let local_index = players
+        .iter()
+        .position(|player| matches!(player, ggrs::PlayerType::Local))
+        .expect("matchbox socket always reports our own player");
+
+    if local_index >= num_players {
+        start_spectator_session(commands, socket, players, num_players);
+        return;
     }
 
     // create a GGRS P2P session
-    let mut session_builder = ggrs::SessionBuilder::<GgrsConfig>::new()
+    let mut session_builder = ggrs::SessionBuilder::<ActiveConfig>::new()
         .with_num_players(num_players)
-        .with_input_delay(2);
+        .with_input_delay(config.input_delay)
+        .with_max_prediction_window(config.max_prediction_window);
 
     for (i, player) in players.into_iter().enumerate() {
-        session_builder = session_builder
-            .add_player(player, i)
-            .expect("failed to add player");
+        session_builder = if i < num_players {
+            session_builder
+                .add_player(player, i)
+                .expect("failed to add player")
+        } else if let ggrs::PlayerType::Remote(peer_id) = player {
+            // peers beyond num_players watch the match without contributing input
+            session_builder
+                .add_player(ggrs::PlayerType::Spectator(peer_id), i)
+                .expect("failed to add spectator")
+        } else {
+            session_builder
+        };
     }
 
     // move the channel out of the socket (required because GGRS takes ownership of it)
@@ -105,11 +369,73 @@ fn wait_for_players(mut commands: Commands, mut socket: ResMut<MatchboxSocket<Si
     }
 }
 
+fn start_spectator_session(
+    mut commands: Commands,
+    mut socket: ResMut<MatchboxSocket<SingleChannel>>,
+    players: Vec<ggrs::PlayerType<PeerId>>,
+    num_players: usize,
+) {
+    // A spectator only needs to follow one of the playing peers; player 0 is
+    // the conventional host for this purpose.
+    let host = players
+        .into_iter()
+        .find_map(|player| match player {
+            ggrs::PlayerType::Remote(peer_id) => Some(peer_id),
+            _ => None,
+        })
+        .expect("no player to spectate yet");
+
+    if let Ok(channel) = socket.take_channel(0) {
+        info!("Joining as a spectator of {:?}", host);
+
+        // GGRS needs the player count to decode the input stream the host
+        // forwards; it must match what the players' own sessions were built
+        // with or the spectator will mis-decode and desync.
+        let ggrs_session = ggrs::SessionBuilder::<ActiveConfig>::new()
+            .with_num_players(num_players)
+            .start_spectator_session(host, channel);
+
+        commands.insert_resource(bevy_ggrs::Session::SpectatorSession(ggrs_session));
+        commands.insert_resource(Spectating(true));
+    }
+}
+
+/// Number of frames GGRS's sync-test session will re-simulate from a saved
+/// snapshot and compare against the originally-computed checksum each frame.
+const SYNCTEST_CHECK_DISTANCE: usize = 7;
+
+fn start_synctest_session(mut commands: Commands, config: Res<GameConfig>) {
+    let num_players = config.num_players;
+
+    // `with_check_distance` must stay below `max_prediction_window` or the
+    // builder hands back `InvalidRequest` instead of a session.
+    let mut session_builder = ggrs::SessionBuilder::<ActiveConfig>::new()
+        .with_num_players(num_players)
+        .with_input_delay(config.input_delay)
+        .with_max_prediction_window(config.max_prediction_window)
+        .with_check_distance(SYNCTEST_CHECK_DISTANCE);
+
+    for i in 0..num_players {
+        session_builder = session_builder
+            .add_player(ggrs::PlayerType::Local, i)
+            .expect("failed to add player");
+    }
+
+    let session = session_builder
+        .start_synctest_session()
+        .expect("failed to start synctest session");
+
+    commands.insert_resource(bevy_ggrs::Session::SyncTestSession(session));
+}
+
 struct GgrsConfig;
 
 impl ggrs::Config for GgrsConfig {
     // 4-directions + fire fits easily in a single byte
     type Input = GameInput;
+    // bevy_ggrs snapshots and restores rollback-registered components itself
+    // (see `register_rollback_component` calls in `main`), so this generic
+    // state type is never actually read; it stays a placeholder.
     type State = u8;
     // Matchbox' WebRtcSocket addresses are called `PeerId`s
     type Address = PeerId;
@@ -140,6 +466,11 @@ pub struct GameInput {
     _padding: [u8; 6],
 }
 
+// Outgrowing the fixed byte layout above (need a variable-length input, or
+// real per-frame state beyond the unused `State = u8` above)? See
+// `serde_config::SerdeGgrsConfig`, built behind the `serde-state` feature,
+// and `benches/packed_vs_bincode.rs` for what it costs in bandwidth.
+
 const INPUT_UP: u8 = 1 << 0;
 const INPUT_DOWN: u8 = 1 << 1;
 const INPUT_LEFT: u8 = 1 << 2;
@@ -153,34 +484,104 @@ struct Player {
 
 fn spawn_players(mut commands: Commands, mut rip: ResMut<RollbackIdProvider>) {
     // Player 1
-    commands.spawn((
-        Player { handle: 0 },
-        rip.next(),
-        SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(-100., 0., 0.)),
-            sprite: Sprite {
-                color: Color::BISQUE,
-                custom_size: Some(Vec2::new(100., 100.)),
+    let player1 = commands
+        .spawn((
+            Player { handle: 0 },
+            rip.next(),
+            Checksum::default(),
+            SpriteBundle {
+                transform: Transform::from_translation(Vec3::new(-100., 0., 0.)),
+                sprite: Sprite {
+                    color: Color::BISQUE,
+                    custom_size: Some(Vec2::new(100., 100.)),
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        },
-    ));
+        ))
+        .id();
 
     // Player 2
-    commands.spawn((
-        Player { handle: 1 },
-        rip.next(),
-        SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(100., 0., 0.)),
-            sprite: Sprite {
-                color: Color::BLUE,
-                custom_size: Some(Vec2::new(100., 100.)),
+    let player2 = commands
+        .spawn((
+            Player { handle: 1 },
+            rip.next(),
+            Checksum::default(),
+            SpriteBundle {
+                transform: Transform::from_translation(Vec3::new(100., 0., 0.)),
+                sprite: Sprite {
+                    color: Color::BLUE,
+                    custom_size: Some(Vec2::new(100., 100.)),
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        },
-    ));
+        ))
+        .id();
+
+    #[cfg(feature = "physics")]
+    for player in [player1, player2] {
+        commands.entity(player).insert((
+            RigidBody::Dynamic,
+            Collider::cuboid(50., 50.),
+            Velocity::default(),
+            LockedAxes::ROTATION_LOCKED,
+            Damping {
+                linear_damping: 4.,
+                angular_damping: 1.,
+            },
+        ));
+    }
+}
+
+/// Bincode snapshot of the Rapier physics world, rolled back alongside the
+/// transforms each GGRS tick. `RapierContext` isn't `Clone`, so rather than
+/// registering it with GGRS directly we roll back this byte buffer and
+/// (de)serialize the real context into it at the edges of the tick, wrapping
+/// Rapier's own sync/step/writeback sets so newly-registered bodies and
+/// colliders are captured too, not just the step's pipeline state.
+#[cfg(feature = "physics")]
+#[derive(Resource, Clone, Default, Reflect)]
+struct RapierContextSnapshot(Vec<u8>);
+
+/// Fixed timestep used for every physics step, independent of the render
+/// frame rate, so resimulating a rollback always reproduces the same motion.
+#[cfg(feature = "physics")]
+const PHYSICS_DT: f32 = 1. / 60.;
+
+#[cfg(feature = "physics")]
+fn restore_rapier_context(
+    snapshot: Res<RapierContextSnapshot>,
+    mut rapier_context: ResMut<RapierContext>,
+) {
+    if !snapshot.0.is_empty() {
+        *rapier_context =
+            bincode::deserialize(&snapshot.0).expect("corrupt Rapier context snapshot");
+    }
+    rapier_context.integration_parameters.dt = PHYSICS_DT;
+}
+
+#[cfg(feature = "physics")]
+fn apply_player_forces(
+    inputs: Res<PlayerInputs<ActiveConfig>>,
+    mut player_query: Query<(&Player, &mut Velocity)>,
+) {
+    for (player, mut velocity) in player_query.iter_mut() {
+        let (input, _) = inputs[player.handle];
+        #[cfg(feature = "serde-state")]
+        let input = serde_config::decode(&input);
+        if input.has_mouse == 1 {
+            velocity.linvel = input.mouse;
+        }
+    }
+}
+
+#[cfg(feature = "physics")]
+fn snapshot_rapier_context(
+    rapier_context: Res<RapierContext>,
+    mut snapshot: ResMut<RapierContextSnapshot>,
+) {
+    snapshot.0 = bincode::serialize(&*rapier_context).expect("failed to snapshot Rapier context");
 }
 
 fn input(
@@ -188,8 +589,19 @@ fn input(
     keys: Res<Input<KeyCode>>,
     window: Query<&Window, With<PrimaryWindow>>,
     camera: Query<(&Camera, &GlobalTransform)>,
-) -> GameInput {
-    let mut input = GameInput { ..default() };
+    spectating: Option<Res<Spectating>>,
+) -> <ActiveConfig as ggrs::Config>::Input {
+    // Spectators don't play, so they have no local input to contribute.
+    if spectating.is_some_and(|s| s.0) {
+        #[cfg(not(feature = "serde-state"))]
+        return GameInput::default();
+        #[cfg(feature = "serde-state")]
+        return serde_config::encode(&serde_config::SerdeGameInput::default());
+    }
+
+    let mut mouse = Vec2::ZERO;
+    let mut has_mouse = 0;
+    let mut keys_pressed = 0;
 
     let (camera, camera_transform) = camera.single();
     if let Some(pos) = window
@@ -198,36 +610,88 @@ fn input(
         .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
         .map(|ray| ray.origin.truncate())
     {
-        input.mouse = pos;
-        input.has_mouse = 1;
+        mouse = pos;
+        has_mouse = 1;
         // info!("Mouse @ {}", pos);
     }
 
     if keys.any_pressed([KeyCode::Up, KeyCode::W]) {
-        input.keys |= INPUT_UP;
+        keys_pressed |= INPUT_UP;
     }
     if keys.any_pressed([KeyCode::Down, KeyCode::S]) {
-        input.keys |= INPUT_DOWN;
+        keys_pressed |= INPUT_DOWN;
     }
     if keys.any_pressed([KeyCode::Left, KeyCode::A]) {
-        input.keys |= INPUT_LEFT
+        keys_pressed |= INPUT_LEFT
     }
     if keys.any_pressed([KeyCode::Right, KeyCode::D]) {
-        input.keys |= INPUT_RIGHT;
+        keys_pressed |= INPUT_RIGHT;
     }
     if keys.any_pressed([KeyCode::Space, KeyCode::Return]) {
-        input.keys |= INPUT_FIRE;
+        keys_pressed |= INPUT_FIRE;
+    }
+
+    #[cfg(not(feature = "serde-state"))]
+    {
+        GameInput {
+            mouse,
+            has_mouse,
+            keys: keys_pressed,
+            ..default()
+        }
     }
+    #[cfg(feature = "serde-state")]
+    {
+        serde_config::encode(&serde_config::SerdeGameInput {
+            mouse,
+            has_mouse,
+            keys: keys_pressed,
+        })
+    }
+}
+
+/// `GGRSPlugin`'s builder doesn't expose a checksum hook in this bevy_ggrs
+/// version (it only has `with_input_system`/`with_update_frequency`/
+/// `register_rollback_{component,resource}`/`build`). Instead we compute a
+/// per-entity digest into this rollback-registered component; bevy_ggrs's
+/// sync-test checksum is the sum of `Reflect::reflect_hash()` over every
+/// registered component/resource, and a bare `#[derive(Reflect)]` returns
+/// `None` from that (no `Hash` impl to fall back on), which would make this
+/// contribute nothing to the comparison. `#[reflect(Hash)]` plus deriving
+/// `Hash` is what actually wires `Checksum` into GGRS's own mismatch check.
+#[derive(Component, Default, Reflect, Hash)]
+#[reflect(Hash)]
+struct Checksum(u64);
 
-    input
+/// Fixed-point scale used to quantize transform floats before hashing them.
+/// Raw float bit patterns can differ between a live frame and its
+/// re-simulated replay even when the values are equivalent, so we round to
+/// this resolution first.
+const CHECKSUM_QUANTIZE: f32 = 1000.0;
+
+fn compute_checksum(mut query: Query<(&Transform, &mut Checksum), With<Rollback>>) {
+    for (transform, mut checksum) in &mut query {
+        let position = (
+            (transform.translation.x * CHECKSUM_QUANTIZE).round() as i64,
+            (transform.translation.y * CHECKSUM_QUANTIZE).round() as i64,
+            (transform.translation.z * CHECKSUM_QUANTIZE).round() as i64,
+        );
+
+        let mut hasher = DefaultHasher::new();
+        position.hash(&mut hasher);
+        checksum.0 = hasher.finish();
+    }
 }
 
+#[cfg(not(feature = "physics"))]
 fn move_players(
-    inputs: Res<PlayerInputs<GgrsConfig>>,
+    inputs: Res<PlayerInputs<ActiveConfig>>,
     mut player_query: Query<(&mut Transform, &Player)>,
 ) {
     for (mut transform, player) in player_query.iter_mut() {
         let (input, _) = inputs[player.handle];
+        #[cfg(feature = "serde-state")]
+        let input = serde_config::decode(&input);
 
         let mut direction = Vec2::ZERO;
 
@@ -251,6 +715,13 @@ fn move_players(
         let _move_delta = (direction * move_speed).extend(0.);
 
         // transform.translation += move_delta;
+        //
+        // Note for anyone chasing desyncs with `--synctest`: this direct
+        // assignment from the networked `input.mouse` is itself deterministic
+        // (same input in, same bits out on resim), so it won't trip the
+        // sync-test checksum on its own. Nondeterminism sneaks in from
+        // platform- or order-dependent floating point *computation* (trig,
+        // normalization, physics), not from copying an already-agreed value.
         if input.has_mouse == 1 {
             transform.translation.x = input.mouse.x;
             transform.translation.y = input.mouse.y;
@@ -0,0 +1,121 @@
+//! Alternative, serde/bincode-based `ggrs::Config`, for games that outgrow
+//! `GameInput`'s fixed packed-byte layout in `main.rs`. Enabled with the
+//! `serde-state` feature. See `benches/packed_vs_bincode.rs` for the
+//! size/throughput tradeoff this buys you over the `Pod`/`Zeroable` path.
+
+use crate::Player;
+use bevy::prelude::*;
+use bevy_matchbox::matchbox_socket::PeerId;
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+/// Variable-length input alternative to `GameInput`. New fields (aim angle,
+/// weapon selection, ...) can be added directly; bincode handles the framing
+/// instead of a fixed `#[repr(C)]` layout with hand-counted `_padding`.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize, Reflect)]
+pub struct SerdeGameInput {
+    pub mouse: Vec2,
+    pub has_mouse: u8,
+    pub keys: u8,
+}
+
+/// `ggrs::Config::Input` must still be `Pod`/`Zeroable` (GGRS transmits it by
+/// byte value), so `SerdeGameInput` itself can't be the wire type. Instead
+/// this wraps a fixed-capacity buffer holding the bincode encoding of a
+/// `SerdeGameInput`, which *can* grow new fields without anyone recomputing
+/// padding by hand, as long as the encoding fits in `ENCODED_INPUT_CAPACITY`.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Pod, Zeroable, Debug, Default)]
+pub struct EncodedInput {
+    bytes: [u8; ENCODED_INPUT_CAPACITY],
+    len: u8,
+    _padding: [u8; 7],
+}
+
+/// Generous headroom over a bare-bones `SerdeGameInput`'s bincode size, so
+/// adding a field or two doesn't immediately require bumping this.
+const ENCODED_INPUT_CAPACITY: usize = 32;
+
+/// Bincode-encodes `input` into a fixed-size `EncodedInput`, called from
+/// inside the GGRS input callback (`main::input`) when `serde-state` is on.
+pub fn encode(input: &SerdeGameInput) -> EncodedInput {
+    let encoded = bincode::serialize(input).expect("failed to encode input");
+    assert!(
+        encoded.len() <= ENCODED_INPUT_CAPACITY,
+        "encoded input ({} bytes) exceeds ENCODED_INPUT_CAPACITY",
+        encoded.len()
+    );
+
+    let mut bytes = [0u8; ENCODED_INPUT_CAPACITY];
+    bytes[..encoded.len()].copy_from_slice(&encoded);
+
+    EncodedInput {
+        bytes,
+        len: encoded.len() as u8,
+        _padding: [0; 7],
+    }
+}
+
+/// Bincode-decodes the `SerdeGameInput` out of an `EncodedInput` received
+/// from `PlayerInputs<SerdeGgrsConfig>`.
+pub fn decode(input: &EncodedInput) -> SerdeGameInput {
+    bincode::deserialize(&input.bytes[..input.len as usize]).expect("corrupt encoded input")
+}
+
+/// One player's slice of simulated world state.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub translation: Vec2,
+}
+
+/// The actual simulated world, bincode-encoded into `GameStateSnapshot` on
+/// every GGRS tick, in place of the unused `u8` placeholder in the
+/// packed-byte `GgrsConfig`. Nothing decodes it back: on rollback the player
+/// transforms are restored by bevy_ggrs's own component rollback, not from
+/// this encoding.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct GameState {
+    pub players: Vec<PlayerState>,
+}
+
+/// Bincode-encoded `GameState`, rolled back like `RapierContextSnapshot`:
+/// `register_rollback_resource` only knows how to save/restore this byte
+/// buffer via `Reflect`, so `save_game_state` is what actually keeps it an
+/// up-to-date bincode encoding of the world each tick.
+#[derive(Resource, Clone, Default, Reflect)]
+pub struct GameStateSnapshot(Vec<u8>);
+
+pub fn save_game_state(
+    player_query: Query<(&Player, &Transform)>,
+    mut snapshot: ResMut<GameStateSnapshot>,
+) {
+    // Sort by handle rather than relying on ECS iteration order, so the
+    // encoding (and thus the checksum a consumer might derive from it) is
+    // the same on a live frame and its re-simulated replay.
+    let mut players: Vec<_> = player_query.iter().collect();
+    players.sort_by_key(|(player, _)| player.handle);
+
+    let state = GameState {
+        players: players
+            .into_iter()
+            .map(|(_, transform)| PlayerState {
+                translation: transform.translation.truncate(),
+            })
+            .collect(),
+    };
+
+    snapshot.0 = bincode::serialize(&state).expect("failed to snapshot game state");
+}
+
+pub struct SerdeGgrsConfig;
+
+impl ggrs::Config for SerdeGgrsConfig {
+    type Input = EncodedInput;
+    // bevy_ggrs snapshots and restores rollback-registered components and
+    // resources itself (see `register_rollback_resource::<GameStateSnapshot>`
+    // in `main`), so this associated type is never read directly by GGRS;
+    // `GameStateSnapshot` is what actually carries its bincode encoding.
+    type State = GameState;
+    // Matchbox' WebRtcSocket addresses are called `PeerId`s
+    type Address = PeerId;
+}